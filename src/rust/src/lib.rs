@@ -2,33 +2,260 @@ use libc::{c_char, c_void};
 use once_cell::sync::Lazy;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::ffi::{CStr, CString};
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{sync_channel, SyncSender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-// Type for callback function that will be called when a message is published
+// Type for callback function that will be called when a message is published.
+// With `publish`, the call may happen on a background worker thread rather
+// than the publisher's thread; use `publish_sync` if the callback must run
+// inline on the calling thread.
 type MessageCallback = extern "C" fn(*const c_char, *const c_char, *mut c_void);
 
 // Global state for our pub/sub system
 static PUBSUB: Lazy<Mutex<PubSubState>> = Lazy::new(|| Mutex::new(PubSubState::new()));
 
+// Paired with `PUBSUB`: notified whenever a message is queued, so `request`
+// can block on a reply inbox's queue without polling.
+static PUBSUB_CONDVAR: Condvar = Condvar::new();
+
+// Fixed-size pool of worker threads that `publish` hands callback
+// invocations off to, fed by a bounded MPSC job queue. `None` until
+// `init_pubsub` is called.
+static WORKER_POOL: Lazy<Mutex<Option<WorkerPool>>> = Lazy::new(|| Mutex::new(None));
+
+// Capacity of the job queue feeding the worker pool; `publish` blocks
+// briefly if every worker is busy and the queue is full.
+const JOB_QUEUE_CAPACITY: usize = 1024;
+
 struct CallbackData(*mut c_void);
 
 // Implement Send and Sync for CallbackData
 unsafe impl Send for CallbackData {}
 unsafe impl Sync for CallbackData {}
 
+// A single pending callback invocation, collected while `PUBSUB` is locked
+// and then run after the lock is released so re-entrant calls into the
+// library (from within a callback) don't deadlock.
+struct CallbackJob {
+    callback: MessageCallback,
+    user_data: CallbackData,
+    topic: CString,
+    message: CString,
+}
+
+struct WorkerPool {
+    sender: SyncSender<CallbackJob>,
+    _handles: Vec<thread::JoinHandle<()>>,
+}
+
+// Start a fixed-size worker pool that background `publish` callback
+// invocations are dispatched to. Safe to call once; returns false if a pool
+// already exists or `num_workers` is zero. Until this is called, `publish`
+// falls back to invoking callbacks inline on the publisher's thread.
+#[no_mangle]
+pub extern "C" fn init_pubsub(num_workers: usize) -> bool {
+    if num_workers == 0 {
+        return false;
+    }
+
+    let mut pool = WORKER_POOL.lock().unwrap();
+    if pool.is_some() {
+        return false;
+    }
+
+    let (sender, receiver) = sync_channel::<CallbackJob>(JOB_QUEUE_CAPACITY);
+    let receiver = Arc::new(Mutex::new(receiver));
+
+    let mut handles = Vec::with_capacity(num_workers);
+    for _ in 0..num_workers {
+        let receiver = Arc::clone(&receiver);
+        handles.push(thread::spawn(move || loop {
+            let job = receiver.lock().unwrap().recv();
+            match job {
+                Ok(job) => {
+                    let cb = job.callback;
+                    cb(job.topic.as_ptr(), job.message.as_ptr(), job.user_data.0);
+                }
+                Err(_) => break, // Sender dropped; shut down.
+            }
+        }));
+    }
+
+    *pool = Some(WorkerPool {
+        sender,
+        _handles: handles,
+    });
+
+    true
+}
+
+// Hand a callback invocation to the worker pool; falls back to running it
+// inline on the current thread if `init_pubsub` hasn't been called or the
+// pool's job queue has been torn down.
+fn dispatch_job(job: CallbackJob) {
+    // Clone the sender and release the guard before the potentially
+    // blocking `send()`: if the job queue is full and a worker's callback
+    // re-enters `publish`, holding `WORKER_POOL` here would deadlock it
+    // against this same lock.
+    let sender = WORKER_POOL.lock().unwrap().as_ref().map(|pool| pool.sender.clone());
+
+    let job = match sender {
+        Some(sender) => match sender.send(job) {
+            Ok(()) => return,
+            Err(err) => err.0,
+        },
+        None => job,
+    };
+
+    let cb = job.callback;
+    cb(job.topic.as_ptr(), job.message.as_ptr(), job.user_data.0);
+}
+
+// A single token of a dot-separated subject pattern, NATS-style:
+// `*` matches exactly one token, `>` matches one or more trailing tokens.
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Literal(String),
+    Star,
+    Tail,
+}
+
+fn parse_pattern(pattern: &str) -> Vec<Token> {
+    pattern
+        .split('.')
+        .map(|tok| match tok {
+            "*" => Token::Star,
+            ">" => Token::Tail,
+            other => Token::Literal(other.to_string()),
+        })
+        .collect()
+}
+
+// Walks the pattern and topic tokens in lockstep. `Tail` must be the final
+// pattern token and consumes all (at least one) remaining topic tokens; a
+// match otherwise requires both arrays to be exhausted simultaneously.
+fn pattern_matches(pattern: &[Token], topic_tokens: &[&str]) -> bool {
+    let mut ti = 0;
+    for (i, tok) in pattern.iter().enumerate() {
+        match tok {
+            Token::Tail => {
+                return i == pattern.len() - 1 && ti < topic_tokens.len();
+            }
+            Token::Star => {
+                if ti >= topic_tokens.len() {
+                    return false;
+                }
+                ti += 1;
+            }
+            Token::Literal(lit) => {
+                if ti >= topic_tokens.len() || topic_tokens[ti] != lit.as_str() {
+                    return false;
+                }
+                ti += 1;
+            }
+        }
+    }
+    ti == topic_tokens.len()
+}
+
+// What to do when a subscriber's queue is at its configured `max_len` and a
+// new message arrives. Mirrors bounded-channel semantics (fixed-capacity
+// ring buffers), e.g. crossbeam's array flavor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum OverflowPolicy {
+    DropNewest,
+    DropOldest,
+    Reject,
+}
+
+impl OverflowPolicy {
+    fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(OverflowPolicy::DropNewest),
+            1 => Some(OverflowPolicy::DropOldest),
+            2 => Some(OverflowPolicy::Reject),
+            _ => None,
+        }
+    }
+}
+
+// A subscriber's message queue, plus its optional capacity and overflow
+// policy. `dropped` counts messages discarded by the policy so Go callers
+// can observe backpressure via `dropped_count`.
+struct SubscriberQueue {
+    messages: VecDeque<(String, Vec<u8>)>,
+    max_len: Option<usize>,
+    policy: OverflowPolicy,
+    dropped: u64,
+}
+
+impl SubscriberQueue {
+    fn new() -> Self {
+        SubscriberQueue {
+            messages: VecDeque::new(),
+            max_len: None,
+            policy: OverflowPolicy::DropNewest,
+            dropped: 0,
+        }
+    }
+
+    // Enqueue a message, applying the overflow policy if the queue is at
+    // capacity: drop-oldest pops the front before pushing, drop-newest
+    // silently discards the incoming message (the publish still counts as
+    // delivered), reject refuses it outright. All three cases record a
+    // drop; reject's additional caller-visible effect is the `false` return, which
+    // `publish`/`publish_bytes` surface by reporting that subscriber's
+    // delivery as failed instead of succeeding silently.
+    fn push(&mut self, entry: (String, Vec<u8>)) -> bool {
+        if let Some(max_len) = self.max_len {
+            if self.messages.len() >= max_len {
+                self.dropped += 1;
+                match self.policy {
+                    OverflowPolicy::DropNewest => return true,
+                    OverflowPolicy::Reject => return false,
+                    OverflowPolicy::DropOldest => {
+                        self.messages.pop_front();
+                    }
+                }
+            }
+        }
+        self.messages.push_back(entry);
+        true
+    }
+}
+
+// Members of a queue group registered via `subscribe_queue` for a given
+// (topic, group_name) pair, plus a round-robin cursor used to pick exactly
+// one recipient per publish.
+struct QueueGroup {
+    members: Vec<String>,
+    cursor: usize,
+}
+
 struct PubSubState {
     // Map of topic to set of subscriber IDs
     topics: HashMap<String, HashSet<String>>,
+    // Map of wildcard pattern string to its parsed tokens and subscriber IDs
+    pattern_topics: HashMap<String, (Vec<Token>, HashSet<String>)>,
+    // Map of (topic, group_name) to its round-robin members
+    queue_groups: HashMap<(String, String), QueueGroup>,
     // Map of subscriber ID to callback function and user data
     callbacks: HashMap<String, (MessageCallback, CallbackData)>,
-    // Queue of messages for subscribers without callbacks
-    message_queues: HashMap<String, VecDeque<(String, String)>>,
+    // Queue of messages for subscribers without callbacks. Payloads are
+    // stored as opaque bytes so binary frames round-trip without corruption;
+    // topics remain UTF-8.
+    message_queues: HashMap<String, SubscriberQueue>,
 }
 
 impl PubSubState {
     fn new() -> Self {
         PubSubState {
             topics: HashMap::new(),
+            pattern_topics: HashMap::new(),
+            queue_groups: HashMap::new(),
             callbacks: HashMap::new(),
             message_queues: HashMap::new(),
         }
@@ -41,6 +268,36 @@ fn c_str_to_string(c_str: *const c_char) -> String {
     c_str.to_string_lossy().into_owned()
 }
 
+// Resolve every subscriber ID whose exact topic or wildcard pattern matches
+// `topic_str`, deduping so a subscriber registered under several matching
+// patterns is only delivered to once.
+fn resolve_subscribers(state: &PubSubState, topic_str: &str) -> HashSet<String> {
+    let topic_tokens: Vec<&str> = topic_str.split('.').collect();
+    let mut subscribers: HashSet<String> = state.topics.get(topic_str).cloned().unwrap_or_default();
+    for (pattern_tokens, pattern_subscribers) in state.pattern_topics.values() {
+        if pattern_matches(pattern_tokens, &topic_tokens) {
+            subscribers.extend(pattern_subscribers.iter().cloned());
+        }
+    }
+    subscribers
+}
+
+// Pick exactly one member from every queue group registered for `topic_str`,
+// advancing that group's round-robin cursor so the next publish picks the
+// next member. Groups with no members are skipped.
+fn pick_queue_group_members(state: &mut PubSubState, topic_str: &str) -> Vec<String> {
+    let mut picked = Vec::new();
+    for ((topic, _group_name), group) in state.queue_groups.iter_mut() {
+        if topic != topic_str || group.members.is_empty() {
+            continue;
+        }
+        let idx = group.cursor % group.members.len();
+        picked.push(group.members[idx].clone());
+        group.cursor = (group.cursor + 1) % group.members.len();
+    }
+    picked
+}
+
 #[no_mangle]
 pub extern "C" fn subscribe(
     subscriber_id: *const c_char,
@@ -74,7 +331,94 @@ pub extern "C" fn subscribe(
         state
             .message_queues
             .entry(subscriber_id.clone())
-            .or_insert_with(VecDeque::new);
+            .or_insert_with(SubscriberQueue::new);
+    }
+
+    true
+}
+
+// Subscribe to a NATS-style wildcard subject pattern (dot-separated tokens,
+// `*` for exactly one token, `>` for one or more trailing tokens). Existing
+// exact-match subscribers registered via `subscribe` are unaffected.
+#[no_mangle]
+pub extern "C" fn subscribe_pattern(
+    subscriber_id: *const c_char,
+    pattern: *const c_char,
+    callback: Option<MessageCallback>,
+    user_data: *mut c_void,
+) -> bool {
+    if subscriber_id.is_null() || pattern.is_null() {
+        return false;
+    }
+
+    let subscriber_id = c_str_to_string(subscriber_id);
+    let pattern = c_str_to_string(pattern);
+
+    let mut state = PUBSUB.lock().unwrap();
+
+    let tokens = parse_pattern(&pattern);
+    let (_, subscribers) = state
+        .pattern_topics
+        .entry(pattern.clone())
+        .or_insert_with(|| (tokens, HashSet::new()));
+    subscribers.insert(subscriber_id.clone());
+
+    if let Some(cb) = callback {
+        state
+            .callbacks
+            .insert(subscriber_id.clone(), (cb, CallbackData(user_data)));
+    } else {
+        state
+            .message_queues
+            .entry(subscriber_id.clone())
+            .or_insert_with(SubscriberQueue::new);
+    }
+
+    true
+}
+
+// Subscribe to a topic as part of a named queue group. Subscribers sharing
+// the same (topic, group_name) form a group; on `publish`, exactly one
+// member of each group receives the message (round-robin), while
+// non-grouped subscribers still get a fan-out copy as usual.
+#[no_mangle]
+pub extern "C" fn subscribe_queue(
+    subscriber_id: *const c_char,
+    topic: *const c_char,
+    group_name: *const c_char,
+    callback: Option<MessageCallback>,
+    user_data: *mut c_void,
+) -> bool {
+    if subscriber_id.is_null() || topic.is_null() || group_name.is_null() {
+        return false;
+    }
+
+    let subscriber_id = c_str_to_string(subscriber_id);
+    let topic = c_str_to_string(topic);
+    let group_name = c_str_to_string(group_name);
+
+    let mut state = PUBSUB.lock().unwrap();
+
+    let group = state
+        .queue_groups
+        .entry((topic, group_name))
+        .or_insert_with(|| QueueGroup {
+            members: Vec::new(),
+            cursor: 0,
+        });
+    if !group.members.contains(&subscriber_id) {
+        group.members.push(subscriber_id.clone());
+    }
+
+    if let Some(cb) = callback {
+        state
+            .callbacks
+            .insert(subscriber_id.clone(), (cb, CallbackData(user_data)));
+    } else {
+        state
+            .message_queues
+            .entry(subscriber_id.clone())
+            .or_insert_with(SubscriberQueue::new);
     }
 
     true
@@ -90,25 +434,106 @@ pub extern "C" fn unsubscribe(subscriber_id: *const c_char, topic: *const c_char
     let mut state = PUBSUB.lock().unwrap();
 
     if topic.is_null() {
-        // Unsubscribe from all topics
+        // Unsubscribe from all topics and patterns
         for (_, subscribers) in state.topics.iter_mut() {
             subscribers.remove(&subscriber_id);
         }
+        for (_, (_, subscribers)) in state.pattern_topics.iter_mut() {
+            subscribers.remove(&subscriber_id);
+        }
+        for group in state.queue_groups.values_mut() {
+            remove_group_member(group, &subscriber_id);
+        }
 
         // Remove callback and message queue
         state.callbacks.remove(&subscriber_id);
         state.message_queues.remove(&subscriber_id);
     } else {
-        // Unsubscribe from specific topic
+        // Unsubscribe from specific topic or pattern
         let topic = c_str_to_string(topic);
         if let Some(subscribers) = state.topics.get_mut(&topic) {
             subscribers.remove(&subscriber_id);
         }
+        if let Some((_, subscribers)) = state.pattern_topics.get_mut(&topic) {
+            subscribers.remove(&subscriber_id);
+        }
+        for ((group_topic, _group_name), group) in state.queue_groups.iter_mut() {
+            if group_topic == &topic {
+                remove_group_member(group, &subscriber_id);
+            }
+        }
     }
 
     true
 }
 
+// Remove a member from a queue group, clamping its round-robin cursor so it
+// still points at a valid member (or 0 if the group is now empty).
+fn remove_group_member(group: &mut QueueGroup, subscriber_id: &str) {
+    if let Some(pos) = group.members.iter().position(|m| m == subscriber_id) {
+        group.members.remove(pos);
+        if group.members.is_empty() {
+            group.cursor = 0;
+        } else {
+            group.cursor %= group.members.len();
+        }
+    }
+}
+
+// Collect the callback jobs and queue pushes for `topic_str`/`message_str`
+// while `PUBSUB` is locked, returning the jobs so the caller can invoke them
+// after releasing the lock, plus whether the message was actually delivered
+// to (or queued for) at least one subscriber — a Reject-policy queue at
+// capacity counts as a failed delivery for that subscriber. Returns `None`
+// if there are no subscribers. `exclude` skips a subscriber ID (used by
+// `publish_excluding`).
+fn prepare_publish(
+    topic_str: &str,
+    message_str: &str,
+    exclude: Option<&str>,
+) -> Option<(Vec<CallbackJob>, bool)> {
+    let mut state = PUBSUB.lock().unwrap();
+
+    let mut subscribers = resolve_subscribers(&state, topic_str);
+    subscribers.extend(pick_queue_group_members(&mut state, topic_str));
+    if let Some(exclude) = exclude {
+        subscribers.remove(exclude);
+    }
+    if subscribers.is_empty() {
+        return None; // No subscribers for this topic
+    }
+
+    // Convert topic and message to C strings once
+    let topic_c_str = CString::new(topic_str).unwrap();
+    let message_c_str = CString::new(message_str).unwrap();
+
+    let mut jobs = Vec::new();
+    let mut delivered = false;
+    for subscriber_id in subscribers {
+        if let Some((callback, user_data)) = state.callbacks.get(&subscriber_id) {
+            jobs.push(CallbackJob {
+                callback: *callback,
+                user_data: CallbackData(user_data.0),
+                topic: topic_c_str.clone(),
+                message: message_c_str.clone(),
+            });
+            delivered = true;
+        } else if let Some(queue) = state.message_queues.get_mut(&subscriber_id) {
+            if queue.push((topic_str.to_string(), message_str.as_bytes().to_vec())) {
+                delivered = true;
+                PUBSUB_CONDVAR.notify_all();
+            }
+        }
+    }
+
+    Some((jobs, delivered))
+}
+
+// Publish a message. Callback subscribers are dispatched to the worker pool
+// started by `init_pubsub` (or invoked inline if the pool hasn't been
+// started) so a slow or re-entrant callback can't stall the publisher or
+// deadlock on `PUBSUB`. Use `publish_sync` when callbacks must run inline,
+// in order, on the calling thread.
 #[no_mangle]
 pub extern "C" fn publish(topic: *const c_char, message: *const c_char) -> bool {
     if topic.is_null() || message.is_null() {
@@ -118,33 +543,107 @@ pub extern "C" fn publish(topic: *const c_char, message: *const c_char) -> bool
     let topic_str = c_str_to_string(topic);
     let message_str = c_str_to_string(message);
 
-    let mut state = PUBSUB.lock().unwrap();
+    let (jobs, delivered) = match prepare_publish(&topic_str, &message_str, None) {
+        Some(result) => result,
+        None => return false,
+    };
+
+    for job in jobs {
+        dispatch_job(job);
+    }
+
+    delivered
+}
+
+// Same as `publish`, but callbacks are invoked inline on the calling thread
+// and have all run before this returns, preserving the ordering guarantees
+// callers relied on before the worker pool was introduced. The order among
+// the subscribers of a single publish is not defined.
+#[no_mangle]
+pub extern "C" fn publish_sync(topic: *const c_char, message: *const c_char) -> bool {
+    if topic.is_null() || message.is_null() {
+        return false;
+    }
+
+    let topic_str = c_str_to_string(topic);
+    let message_str = c_str_to_string(message);
 
-    // Check if topic exists
-    let subscribers = match state.topics.get(&topic_str) {
-        Some(subs) => subs.clone(), // Clone the subscribers to avoid borrow issues
-        None => return false,       // Topic doesn't exist
+    let (jobs, delivered) = match prepare_publish(&topic_str, &message_str, None) {
+        Some(result) => result,
+        None => return false,
     };
 
-    // Convert topic and message to C strings once
-    let topic_c_str = CString::new(topic_str.clone()).unwrap();
-    let message_c_str = CString::new(message_str.clone()).unwrap();
+    for job in jobs {
+        let cb = job.callback;
+        cb(job.topic.as_ptr(), job.message.as_ptr(), job.user_data.0);
+    }
 
-    // Process each subscriber
-    for subscriber_id in subscribers {
-        // If subscriber has a callback, invoke it
-        if let Some((callback, user_data)) = state.callbacks.get(&subscriber_id) {
-            let cb = *callback;
-            cb(topic_c_str.as_ptr(), message_c_str.as_ptr(), user_data.0);
-        } else {
-            // Otherwise, queue the message
-            if let Some(queue) = state.message_queues.get_mut(&subscriber_id) {
-                queue.push_back((topic_str.clone(), message_str.clone()));
+    delivered
+}
+
+// Binary-safe counterpart to `publish`: the payload is an opaque byte slice
+// rather than a NUL-terminated C string, so embedded NULs and non-UTF-8
+// frames (protobuf, msgpack, ...) round-trip without truncation. The topic
+// is still UTF-8. Callback subscribers are invoked with a best-effort
+// NUL-terminated view of the payload and are skipped if it contains an
+// embedded NUL; queued subscribers always receive the payload intact.
+#[no_mangle]
+pub extern "C" fn publish_bytes(
+    topic: *const c_char,
+    message: *const u8,
+    message_len: usize,
+) -> bool {
+    if topic.is_null() || (message.is_null() && message_len > 0) {
+        return false;
+    }
+
+    let topic_str = c_str_to_string(topic);
+    let message_bytes = if message_len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(message, message_len) }.to_vec()
+    };
+
+    let (jobs, delivered) = {
+        let mut state = PUBSUB.lock().unwrap();
+
+        let mut subscribers = resolve_subscribers(&state, &topic_str);
+        subscribers.extend(pick_queue_group_members(&mut state, &topic_str));
+        if subscribers.is_empty() {
+            return false; // No subscribers for this topic
+        }
+
+        let topic_c_str = CString::new(topic_str.clone()).unwrap();
+        let message_c_str = CString::new(message_bytes.clone()).ok();
+
+        let mut jobs = Vec::new();
+        let mut delivered = false;
+        for subscriber_id in subscribers {
+            if let Some((callback, user_data)) = state.callbacks.get(&subscriber_id) {
+                if let Some(message_c_str) = &message_c_str {
+                    jobs.push(CallbackJob {
+                        callback: *callback,
+                        user_data: CallbackData(user_data.0),
+                        topic: topic_c_str.clone(),
+                        message: message_c_str.clone(),
+                    });
+                    delivered = true;
+                }
+            } else if let Some(queue) = state.message_queues.get_mut(&subscriber_id) {
+                if queue.push((topic_str.clone(), message_bytes.clone())) {
+                    delivered = true;
+                    PUBSUB_CONDVAR.notify_all();
+                }
             }
         }
+        (jobs, delivered)
+    };
+
+    for job in jobs {
+        dispatch_job(job);
     }
 
-    true
+    delivered
 }
 
 #[no_mangle]
@@ -170,8 +669,8 @@ pub extern "C" fn get_next_message(
             let topic_str = c_str_to_string(topic);
 
             // Find the index of the first message for this topic
-            if let Some(index) = queue.iter().position(|(t, _)| t == &topic_str) {
-                let (topic, message) = queue.remove(index).unwrap();
+            if let Some(index) = queue.messages.iter().position(|(t, _)| t == &topic_str) {
+                let (topic, message) = queue.messages.remove(index).unwrap();
 
                 // Copy topic and message to output buffers if provided
                 if !out_topic.is_null() && out_topic_size > 0 {
@@ -200,7 +699,7 @@ pub extern "C" fn get_next_message(
 
                 return true;
             }
-        } else if let Some((topic, message)) = queue.pop_front() {
+        } else if let Some((topic, message)) = queue.messages.pop_front() {
             // Get the next message regardless of topic
             // Copy topic and message to output buffers if provided
             if !out_topic.is_null() && out_topic_size > 0 {
@@ -234,6 +733,77 @@ pub extern "C" fn get_next_message(
     false
 }
 
+// Binary-safe counterpart to `get_next_message`. Writes the full payload
+// (which may contain embedded NULs) into `out_message` and, whenever a
+// matching message is found, reports its true payload length via
+// `out_message_len` (left untouched if there is none). If `out_message_size` is
+// too small to hold it, the message is left on the queue, `out_message_len`
+// is set to the required size, and the caller should retry with a larger
+// buffer.
+#[no_mangle]
+pub extern "C" fn get_next_message_bytes(
+    subscriber_id: *const c_char,
+    topic: *const c_char,
+    out_topic: *mut c_char,
+    out_topic_size: usize,
+    out_message: *mut u8,
+    out_message_size: usize,
+    out_message_len: *mut usize,
+) -> bool {
+    if subscriber_id.is_null() || out_message_len.is_null() {
+        return false;
+    }
+
+    let subscriber_id = c_str_to_string(subscriber_id);
+    let mut state = PUBSUB.lock().unwrap();
+
+    let queue = match state.message_queues.get_mut(&subscriber_id) {
+        Some(queue) => queue,
+        None => return false,
+    };
+
+    let index = if topic.is_null() {
+        if queue.messages.is_empty() {
+            None
+        } else {
+            Some(0)
+        }
+    } else {
+        let topic_str = c_str_to_string(topic);
+        queue.messages.iter().position(|(t, _)| t == &topic_str)
+    };
+
+    let index = match index {
+        Some(index) => index,
+        None => return false,
+    };
+
+    // Peek without removing in case the output buffer is too small.
+    let required_len = queue.messages[index].1.len();
+    unsafe {
+        *out_message_len = required_len;
+    }
+    if out_message.is_null() || out_message_size < required_len {
+        return false;
+    }
+
+    let (topic, message) = queue.messages.remove(index).unwrap();
+
+    if !out_topic.is_null() && out_topic_size > 0 {
+        let bytes_to_copy = std::cmp::min(topic.len(), out_topic_size - 1);
+        unsafe {
+            std::ptr::copy_nonoverlapping(topic.as_ptr(), out_topic as *mut u8, bytes_to_copy);
+            *out_topic.add(bytes_to_copy) = 0; // Null terminator
+        }
+    }
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(message.as_ptr(), out_message, required_len);
+    }
+
+    true
+}
+
 #[no_mangle]
 pub extern "C" fn has_messages(subscriber_id: *const c_char, topic: *const c_char) -> bool {
     if subscriber_id.is_null() {
@@ -246,13 +816,431 @@ pub extern "C" fn has_messages(subscriber_id: *const c_char, topic: *const c_cha
     if let Some(queue) = state.message_queues.get(&subscriber_id) {
         if topic.is_null() {
             // Check if there are any messages
-            return !queue.is_empty();
+            return !queue.messages.is_empty();
         } else {
             // Check if there are messages for the specific topic
             let topic_str = c_str_to_string(topic);
-            return queue.iter().any(|(t, _)| t == &topic_str);
+            return queue.messages.iter().any(|(t, _)| t == &topic_str);
         }
     }
 
     false
 }
+
+// Bound a subscriber's message queue and choose what happens when a publish
+// would push past `max_len`: 0 = drop newest (skip the incoming message),
+// 1 = drop oldest (evict the front to make room), 2 = reject (skip the
+// incoming message and record the drop, but unlike drop-newest report that
+// subscriber's delivery as failed rather than successful). Applies only
+// to subscribers without a callback, since callback subscribers are never
+// queued. Returns false for an unknown subscriber or policy value.
+#[no_mangle]
+pub extern "C" fn set_queue_limit(
+    subscriber_id: *const c_char,
+    max_len: usize,
+    policy: i32,
+) -> bool {
+    if subscriber_id.is_null() {
+        return false;
+    }
+
+    let policy = match OverflowPolicy::from_i32(policy) {
+        Some(policy) => policy,
+        None => return false,
+    };
+
+    let subscriber_id = c_str_to_string(subscriber_id);
+    let mut state = PUBSUB.lock().unwrap();
+
+    let queue = match state.message_queues.get_mut(&subscriber_id) {
+        Some(queue) => queue,
+        None => return false,
+    };
+
+    queue.max_len = Some(max_len);
+    queue.policy = policy;
+
+    true
+}
+
+// Number of messages dropped from a subscriber's queue by its overflow
+// policy so far. Returns 0 for an unknown subscriber.
+#[no_mangle]
+pub extern "C" fn dropped_count(subscriber_id: *const c_char) -> u64 {
+    if subscriber_id.is_null() {
+        return 0;
+    }
+
+    let subscriber_id = c_str_to_string(subscriber_id);
+    let state = PUBSUB.lock().unwrap();
+
+    state
+        .message_queues
+        .get(&subscriber_id)
+        .map(|queue| queue.dropped)
+        .unwrap_or(0)
+}
+
+// Prefix marking a topic as a transient `request` reply inbox rather than a
+// real subscription, so introspection APIs (`topic_list`, `subscriber_count`)
+// can filter it out.
+const INBOX_PREFIX: &str = "_INBOX.";
+
+// Auto-incrementing counter mixed into generated reply-inbox names so they
+// stay collision-resistant even under concurrent `request` calls.
+static INBOX_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn generate_inbox() -> String {
+    let counter = INBOX_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    format!("{INBOX_PREFIX}{counter}.{nanos:x}")
+}
+
+// Drop a reply inbox's exact-match topic entry and message queue.
+fn remove_inbox(inbox: &str) {
+    let mut state = PUBSUB.lock().unwrap();
+    state.topics.remove(inbox);
+    state.message_queues.remove(inbox);
+}
+
+// Request/reply on top of the bus, NATS-style: publishes `message` to
+// `topic` tagged with an auto-generated, collision-resistant reply inbox
+// (the inbox name followed by a newline, prepended to the message), then
+// blocks the calling thread until a reply lands on that inbox or
+// `timeout_ms` elapses. The generated inbox name is also written back
+// through `reply_inbox_out` (if non-null) so the caller can log it.
+// Responders split the incoming message on its first newline to recover
+// the inbox and reply with the normal `publish(reply_inbox, response)`.
+// Returns false on timeout or if `topic` has no subscribers.
+#[no_mangle]
+pub extern "C" fn request(
+    topic: *const c_char,
+    message: *const c_char,
+    reply_inbox_out: *mut c_char,
+    reply_inbox_out_size: usize,
+    timeout_ms: u64,
+    out_message: *mut c_char,
+    out_message_size: usize,
+) -> bool {
+    if topic.is_null() || message.is_null() {
+        return false;
+    }
+
+    let message_str = c_str_to_string(message);
+    let inbox = generate_inbox();
+
+    if !reply_inbox_out.is_null() && reply_inbox_out_size > 0 {
+        let bytes_to_copy = std::cmp::min(inbox.len(), reply_inbox_out_size - 1);
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                inbox.as_ptr(),
+                reply_inbox_out as *mut u8,
+                bytes_to_copy,
+            );
+            *reply_inbox_out.add(bytes_to_copy) = 0;
+        }
+    }
+
+    // Subscribe to the reply inbox with no callback, using the inbox name
+    // itself as the (anonymous) subscriber ID, so replies land in a queue
+    // we can wait on.
+    {
+        let mut state = PUBSUB.lock().unwrap();
+        state
+            .topics
+            .entry(inbox.clone())
+            .or_insert_with(HashSet::new)
+            .insert(inbox.clone());
+        state
+            .message_queues
+            .entry(inbox.clone())
+            .or_insert_with(SubscriberQueue::new);
+    }
+
+    let tagged_message = CString::new(format!("{inbox}\n{message_str}")).unwrap();
+    if !publish_sync(topic, tagged_message.as_ptr()) {
+        remove_inbox(&inbox);
+        return false;
+    }
+
+    let state = PUBSUB.lock().unwrap();
+    let (mut state, _wait_result) = PUBSUB_CONDVAR
+        .wait_timeout_while(state, Duration::from_millis(timeout_ms), |state| {
+            state
+                .message_queues
+                .get(&inbox)
+                .map(|queue| queue.messages.is_empty())
+                .unwrap_or(true)
+        })
+        .unwrap();
+
+    let reply = state
+        .message_queues
+        .get_mut(&inbox)
+        .and_then(|queue| queue.messages.pop_front());
+    state.topics.remove(&inbox);
+    state.message_queues.remove(&inbox);
+    drop(state);
+
+    match reply {
+        Some((_, payload)) => {
+            if !out_message.is_null() && out_message_size > 0 {
+                let bytes_to_copy = std::cmp::min(payload.len(), out_message_size - 1);
+                unsafe {
+                    std::ptr::copy_nonoverlapping(
+                        payload.as_ptr(),
+                        out_message as *mut u8,
+                        bytes_to_copy,
+                    );
+                    *out_message.add(bytes_to_copy) = 0;
+                }
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+// Same as `publish`, but skips `exclude_subscriber_id` when delivering —
+// useful for chat/presence fan-out where a subscriber that is also the
+// publisher shouldn't receive its own message back.
+#[no_mangle]
+pub extern "C" fn publish_excluding(
+    topic: *const c_char,
+    message: *const c_char,
+    exclude_subscriber_id: *const c_char,
+) -> bool {
+    if topic.is_null() || message.is_null() {
+        return false;
+    }
+
+    let topic_str = c_str_to_string(topic);
+    let message_str = c_str_to_string(message);
+    let exclude = if exclude_subscriber_id.is_null() {
+        None
+    } else {
+        Some(c_str_to_string(exclude_subscriber_id))
+    };
+
+    let (jobs, delivered) = match prepare_publish(&topic_str, &message_str, exclude.as_deref()) {
+        Some(result) => result,
+        None => return false,
+    };
+
+    for job in jobs {
+        dispatch_job(job);
+    }
+
+    delivered
+}
+
+// Number of subscribers currently registered for the exact topic `topic`
+// via `subscribe` (wildcard pattern subscribers and queue group members
+// aren't counted). Returns 0 for an unknown
+// topic or a transient `request` reply inbox.
+#[no_mangle]
+pub extern "C" fn subscriber_count(topic: *const c_char) -> usize {
+    if topic.is_null() {
+        return 0;
+    }
+
+    let topic_str = c_str_to_string(topic);
+    if topic_str.starts_with(INBOX_PREFIX) {
+        return 0;
+    }
+
+    let state = PUBSUB.lock().unwrap();
+
+    state
+        .topics
+        .get(&topic_str)
+        .map(|subscribers| subscribers.len())
+        .unwrap_or(0)
+}
+
+// Serialize the names of all known exact-match topics into `out_buffer`,
+// newline-separated and NUL-terminated, truncating to `out_buffer_size` if
+// necessary. Transient `request` reply inboxes are excluded, since they
+// aren't real subscriptions. Always returns the length required to hold the
+// full list (excluding the NUL terminator); if that's >= `out_buffer_size`,
+// the caller should retry with a larger buffer.
+#[no_mangle]
+pub extern "C" fn topic_list(out_buffer: *mut c_char, out_buffer_size: usize) -> usize {
+    let state = PUBSUB.lock().unwrap();
+
+    let mut names: Vec<&str> = state
+        .topics
+        .keys()
+        .map(|s| s.as_str())
+        .filter(|topic| !topic.starts_with(INBOX_PREFIX))
+        .collect();
+    names.sort_unstable();
+    let joined = names.join("\n");
+
+    if !out_buffer.is_null() && out_buffer_size > 0 {
+        let bytes_to_copy = std::cmp::min(joined.len(), out_buffer_size - 1);
+        unsafe {
+            std::ptr::copy_nonoverlapping(joined.as_ptr(), out_buffer as *mut u8, bytes_to_copy);
+            *out_buffer.add(bytes_to_copy) = 0;
+        }
+    }
+
+    joined.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, topic: &str) -> bool {
+        let tokens = parse_pattern(pattern);
+        let topic_tokens: Vec<&str> = topic.split('.').collect();
+        pattern_matches(&tokens, &topic_tokens)
+    }
+
+    #[test]
+    fn literal_pattern_requires_exact_match() {
+        assert!(matches("a.b.c", "a.b.c"));
+        assert!(!matches("a.b.c", "a.b"));
+        assert!(!matches("a.b.c", "a.b.c.d"));
+        assert!(!matches("a.b.c", "a.b.x"));
+    }
+
+    #[test]
+    fn star_matches_exactly_one_token() {
+        assert!(matches("a.*.c", "a.b.c"));
+        assert!(!matches("a.*.c", "a.c"));
+        assert!(!matches("a.*.c", "a.b.b.c"));
+    }
+
+    #[test]
+    fn tail_matches_one_or_more_trailing_tokens() {
+        assert!(matches("a.>", "a.b"));
+        assert!(matches("a.>", "a.b.c"));
+        assert!(!matches("a.>", "a"));
+    }
+
+    #[test]
+    fn tail_must_be_final_token_to_consume_rest() {
+        // `>` only gets tail semantics as the last pattern token; this checks
+        // the exhaustion check doesn't accidentally let a mid-pattern survive.
+        let tokens = vec![Token::Tail, Token::Literal("c".to_string())];
+        assert!(!pattern_matches(&tokens, &["a", "b", "c"]));
+    }
+
+    #[test]
+    fn empty_pattern_matches_only_empty_topic() {
+        assert!(matches("", ""));
+        assert!(!matches("", "a"));
+    }
+
+    fn queue_with(max_len: usize, policy: OverflowPolicy) -> SubscriberQueue {
+        let mut queue = SubscriberQueue::new();
+        queue.max_len = Some(max_len);
+        queue.policy = policy;
+        queue
+    }
+
+    fn msg(n: u8) -> (String, Vec<u8>) {
+        ("t".to_string(), vec![n])
+    }
+
+    #[test]
+    fn drop_newest_keeps_oldest_and_reports_success() {
+        let mut queue = queue_with(2, OverflowPolicy::DropNewest);
+        assert!(queue.push(msg(1)));
+        assert!(queue.push(msg(2)));
+        // Queue is full; the incoming message is silently dropped but the
+        // push still reports success (the publish "delivered" overall).
+        assert!(queue.push(msg(3)));
+        assert_eq!(queue.messages, VecDeque::from([msg(1), msg(2)]));
+        assert_eq!(queue.dropped, 1);
+    }
+
+    #[test]
+    fn drop_oldest_evicts_front_and_reports_success() {
+        let mut queue = queue_with(2, OverflowPolicy::DropOldest);
+        assert!(queue.push(msg(1)));
+        assert!(queue.push(msg(2)));
+        assert!(queue.push(msg(3)));
+        assert_eq!(queue.messages, VecDeque::from([msg(2), msg(3)]));
+        assert_eq!(queue.dropped, 1);
+    }
+
+    #[test]
+    fn reject_refuses_and_reports_failure() {
+        let mut queue = queue_with(2, OverflowPolicy::Reject);
+        assert!(queue.push(msg(1)));
+        assert!(queue.push(msg(2)));
+        // Queue is full; reject must be observably distinct from drop-newest
+        // by returning false instead of silently succeeding.
+        assert!(!queue.push(msg(3)));
+        assert_eq!(queue.messages, VecDeque::from([msg(1), msg(2)]));
+        assert_eq!(queue.dropped, 1);
+    }
+
+    #[test]
+    fn unbounded_queue_never_drops() {
+        let mut queue = SubscriberQueue::new();
+        for n in 0..10 {
+            assert!(queue.push(msg(n)));
+        }
+        assert_eq!(queue.messages.len(), 10);
+        assert_eq!(queue.dropped, 0);
+    }
+
+    fn group(members: &[&str]) -> QueueGroup {
+        QueueGroup {
+            members: members.iter().map(|s| s.to_string()).collect(),
+            cursor: 0,
+        }
+    }
+
+    #[test]
+    fn queue_group_picks_members_round_robin() {
+        let mut state = PubSubState::new();
+        state
+            .queue_groups
+            .insert(("t".to_string(), "g".to_string()), group(&["a", "b", "c"]));
+
+        assert_eq!(pick_queue_group_members(&mut state, "t"), vec!["a"]);
+        assert_eq!(pick_queue_group_members(&mut state, "t"), vec!["b"]);
+        assert_eq!(pick_queue_group_members(&mut state, "t"), vec!["c"]);
+        // Cursor wraps back to the first member.
+        assert_eq!(pick_queue_group_members(&mut state, "t"), vec!["a"]);
+    }
+
+    #[test]
+    fn queue_group_skips_other_topics_and_empty_groups() {
+        let mut state = PubSubState::new();
+        state
+            .queue_groups
+            .insert(("t".to_string(), "g".to_string()), group(&["a"]));
+        state
+            .queue_groups
+            .insert(("other".to_string(), "g".to_string()), group(&["b"]));
+        state
+            .queue_groups
+            .insert(("t".to_string(), "empty".to_string()), group(&[]));
+
+        assert_eq!(pick_queue_group_members(&mut state, "t"), vec!["a"]);
+    }
+
+    #[test]
+    fn queue_group_picks_one_member_per_group_for_same_topic() {
+        let mut state = PubSubState::new();
+        state
+            .queue_groups
+            .insert(("t".to_string(), "g1".to_string()), group(&["a", "b"]));
+        state
+            .queue_groups
+            .insert(("t".to_string(), "g2".to_string()), group(&["x", "y"]));
+
+        let mut picked = pick_queue_group_members(&mut state, "t");
+        picked.sort();
+        assert_eq!(picked, vec!["a", "x"]);
+    }
+}